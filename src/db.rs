@@ -5,10 +5,37 @@ use crate::types::{RedbKey, RedbValue};
 use crate::Error;
 use memmap2::MmapMut;
 use std::fs::OpenOptions;
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
 use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
 
 pub struct Database {
     storage: Storage,
+    write_lock: Mutex<()>,
+}
+
+/// Controls how aggressively [`Storage::commit`] syncs the mmap to disk, trading
+/// crash-consistency for throughput. The three variants are passed straight through to
+/// `Storage::new`; the branching on commit is implemented there, not in this file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Durability {
+    /// Flush the mmap to disk before every commit returns. This is the default, and matches
+    /// the implicit behavior redb has always had.
+    Immediate,
+    /// Batch flushes, syncing on a background cadence or when the database is dropped, instead
+    /// of on every commit. A crash can lose the most recent commits.
+    Eventual,
+    /// Never sync explicitly; rely entirely on the OS to write pages back on its own schedule.
+    /// Useful for throwaway or bulk-load workloads where the file can simply be discarded and
+    /// redone if the process is interrupted.
+    None,
+}
+
+impl Default for Durability {
+    fn default() -> Self {
+        Durability::Immediate
+    }
 }
 
 impl Database {
@@ -17,9 +44,10 @@ impl Database {
     /// * if the file is a valid redb database, it will be opened
     /// * otherwise this function will return an error
     ///
-    /// `db_size`: the maximum size in bytes of the database. Note: this cannot be changed after the
-    /// database is created.
-    /// TODO: remove the restriction that db_size cannot be changed
+    /// `db_size`: the initial size in bytes to allocate for a newly-created database. Once a
+    /// write can't be satisfied by the current allocation, the storage layer grows the file and
+    /// remaps it at a transaction boundary, so this is a sizing hint rather than a hard cap; use
+    /// [`DatabaseBuilder::set_growth_step`] to control the step size it grows by.
     ///
     /// # Safety
     ///
@@ -35,44 +63,101 @@ impl Database {
         file.set_len(db_size as u64)?;
 
         let mmap = MmapMut::map_mut(&file)?;
-        let storage = Storage::new(mmap, None)?;
-        Ok(Database { storage })
+        let storage = Storage::new(mmap, None, None, None, Durability::default())?;
+        Ok(Database {
+            storage,
+            write_lock: Mutex::new(()),
+        })
+    }
+
+    /// Begins a database-level write transaction.
+    ///
+    /// Unlike [`Self::open_table`], the returned [`WriteTransaction`] can open any number of
+    /// tables (via [`WriteTransaction::open_table`] / [`WriteTransaction::open_multimap_table`])
+    /// under one [`WriteTransaction::commit`] call; see that method's doc for what it does and
+    /// does not guarantee across tables. Two `begin_write` calls on this `Database` are
+    /// serialized against each other — the second blocks until the first's `WriteTransaction` is
+    /// committed or dropped — but that serialization does not extend to a [`Table`] obtained via
+    /// [`Self::open_table`] being written through directly afterward; see that method's doc.
+    pub fn begin_write(&self) -> Result<WriteTransaction, Error> {
+        let guard = self.write_lock.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        WriteTransaction::new(&self.storage, guard)
+    }
+
+    /// Begins a database-level read transaction. See [`ReadTransaction`] for which reads use the
+    /// root captured here and which see the latest committed state.
+    pub fn begin_read(&self) -> Result<ReadTransaction, Error> {
+        Ok(ReadTransaction::new(&self.storage))
     }
 
+    /// Opens `name` as a table, implicitly creating and committing a single-table write
+    /// transaction. This is a convenience wrapper around [`Self::begin_write`] for callers that
+    /// only need to touch one table; use `begin_write` directly to open several.
+    ///
+    /// Opening the table goes through [`Self::begin_write`], so it is serialized against other
+    /// writers, but the returned [`Table`] handle is not: its own `begin_write`/`commit` (used
+    /// for every write made through it afterward) calls straight into the storage layer and
+    /// isn't tracked by `Database`'s write-serialization at all, since by the time the caller
+    /// uses it `Database` has no way to hold a lock over a handle it has already returned.
+    // TODO: this could still conflict with an on-going write made through the returned `Table`
+    // handle, or through another `Table` opened for the same underlying table.
     pub fn open_table<K: RedbKey + ?Sized, V: RedbValue + ?Sized>(
         &self,
         name: impl AsRef<[u8]>,
     ) -> Result<Table<K, V>, Error> {
-        assert!(!name.as_ref().is_empty());
-        // TODO: this could conflict with an on-going write
-        let id = self.storage.allocate_write_transaction();
-        let (definition, root) = self.storage.get_or_create_table(
-            name.as_ref(),
-            TableType::Normal,
-            id,
-            self.storage.get_root_page_number(),
-        )?;
-        self.storage.commit(Some(root), id)?;
-        Table::new(definition.get_id(), &self.storage)
+        let mut txn = self.begin_write()?;
+        let table = txn.open_table(name)?;
+        txn.commit()?;
+        Ok(table)
     }
 
+    /// Opens `name` as a multimap table, implicitly creating and committing a single-table
+    /// write transaction. See [`Self::open_table`] for when to prefer [`Self::begin_write`]
+    /// instead.
     pub fn open_multimap_table<K: RedbKey + ?Sized, V: RedbKey + ?Sized>(
         &self,
         name: impl AsRef<[u8]>,
     ) -> Result<MultiMapTable<K, V>, Error> {
-        assert!(!name.as_ref().is_empty());
-        // TODO: this could conflict with an on-going write
-        let id = self.storage.allocate_write_transaction();
-        let (definition, root) = self.storage.get_or_create_table(
-            name.as_ref(),
-            TableType::MultiMap,
-            id,
-            self.storage.get_root_page_number(),
-        )?;
-        self.storage.commit(Some(root), id)?;
-        MultiMapTable::new(definition.get_id(), &self.storage)
+        let mut txn = self.begin_write()?;
+        let table = txn.open_multimap_table(name)?;
+        txn.commit()?;
+        Ok(table)
+    }
+
+    /// Opens `name` as a table keyed by a fixed-width primitive integer, implicitly creating
+    /// and committing a single-table write transaction. See [`IntegerTable`] for details on the
+    /// key encoding.
+    pub fn open_integer_table<K: PrimitiveInt, V: RedbValue + ?Sized>(
+        &self,
+        name: impl AsRef<[u8]>,
+    ) -> Result<IntegerTable<K, V>, Error> {
+        let mut txn = self.begin_write()?;
+        let table = txn.open_integer_table(name)?;
+        txn.commit()?;
+        Ok(table)
+    }
+
+    /// Compacts this database into a freshly created file at `dest`.
+    ///
+    /// Only pages reachable from the current committed root are copied, laid out densely
+    /// starting at page 0, with internal references rewritten to the new page numbers. The
+    /// result is a valid redb file sized to the live data plus a small margin, which is useful
+    /// to reclaim space left behind by the copy-on-write B-tree's stale pages. The source
+    /// database is left untouched. See [`Self::stats`] for overall storage statistics.
+    pub fn compact(&self, dest: impl AsRef<Path>) -> Result<(), Error> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(dest)?;
+        self.storage.compact_into(file)
     }
 
+    /// Returns current storage statistics.
+    // TODO: DbStats doesn't yet break out live vs. allocated byte counts, which would make it
+    // easy to tell whether `compact` is worth running; add that split in the tree_store DbStats
+    // definition.
     pub fn stats(&self) -> Result<DbStats, Error> {
         self.storage.storage_stats()
     }
@@ -82,14 +167,232 @@ impl Database {
     }
 }
 
+/// A database-level write transaction, grouping access to any number of tables under one
+/// consistent starting point.
+///
+/// Tables are opened against the transaction via [`Self::open_table`] /
+/// [`Self::open_multimap_table`] / [`Self::open_integer_table`]. Each returned handle is a
+/// plain [`Table`]/[`MultiMapTable`]/[`IntegerTable`], the same type [`Database::open_table`]
+/// hands out, and its own `begin_write`/`commit` independently flushes that table's writes to
+/// storage and makes them visible to other readers right away — this transaction does not
+/// batch or defer them. [`Self::commit`] only finalizes the transaction's view of the current
+/// root *after* re-reading it from storage, so it reflects whatever the tables opened through
+/// it have already committed instead of the (possibly stale) root captured when each table was
+/// opened; it does not provide atomicity across tables.
+pub struct WriteTransaction<'a> {
+    storage: &'a Storage,
+    id: u128,
+    root: Option<u64>,
+    // Held for the lifetime of the transaction so that a second `Database::begin_write` call
+    // blocks until this one is committed or dropped, rather than racing it.
+    _write_guard: MutexGuard<'a, ()>,
+}
+
+impl<'a> WriteTransaction<'a> {
+    fn new(storage: &'a Storage, write_guard: MutexGuard<'a, ()>) -> Result<WriteTransaction<'a>, Error> {
+        let id = storage.allocate_write_transaction();
+        let root = storage.get_root_page_number();
+        Ok(WriteTransaction {
+            storage,
+            id,
+            root,
+            _write_guard: write_guard,
+        })
+    }
+
+    pub fn open_table<K: RedbKey + ?Sized, V: RedbValue + ?Sized>(
+        &mut self,
+        name: impl AsRef<[u8]>,
+    ) -> Result<Table<K, V>, Error> {
+        assert!(!name.as_ref().is_empty());
+        let (definition, root) =
+            self.storage
+                .get_or_create_table(name.as_ref(), TableType::Normal, self.id, self.root)?;
+        self.root = Some(root);
+        Table::new(definition.get_id(), self.storage)
+    }
+
+    pub fn open_multimap_table<K: RedbKey + ?Sized, V: RedbKey + ?Sized>(
+        &mut self,
+        name: impl AsRef<[u8]>,
+    ) -> Result<MultiMapTable<K, V>, Error> {
+        assert!(!name.as_ref().is_empty());
+        let (definition, root) =
+            self.storage
+                .get_or_create_table(name.as_ref(), TableType::MultiMap, self.id, self.root)?;
+        self.root = Some(root);
+        MultiMapTable::new(definition.get_id(), self.storage)
+    }
+
+    /// Opens `name` as a table keyed by a fixed-width primitive integer. See [`IntegerTable`]
+    /// for details on the key encoding.
+    pub fn open_integer_table<K: PrimitiveInt, V: RedbValue + ?Sized>(
+        &mut self,
+        name: impl AsRef<[u8]>,
+    ) -> Result<IntegerTable<K, V>, Error> {
+        Ok(IntegerTable::new(self.open_table(name)?))
+    }
+
+    /// Finalizes this transaction.
+    ///
+    /// Re-reads the current root from storage rather than relying on the value captured the
+    /// last time a table was opened, since any of those tables may have independently committed
+    /// writes (and therefore advanced the root) afterward — committing the stale, captured value
+    /// here would silently discard those writes or be rejected as out of date.
+    pub fn commit(self) -> Result<(), Error> {
+        let root = self.storage.get_root_page_number();
+        self.storage.commit(root, self.id)
+    }
+}
+
+/// A database-level read transaction.
+///
+/// The root is captured when the transaction begins, and table names are looked up in it.
+/// Each handle returned is a plain [`Table`]/[`MultiMapTable`]/[`IntegerTable`] bound only to
+/// the table's id and the storage, not to that root: its own `read_transaction` reads the
+/// latest committed state at the time it is called, so reads through different handles are
+/// not pinned to one snapshot.
+pub struct ReadTransaction<'a> {
+    storage: &'a Storage,
+    root: Option<u64>,
+}
+
+impl<'a> ReadTransaction<'a> {
+    fn new(storage: &'a Storage) -> ReadTransaction<'a> {
+        let root = storage.get_root_page_number();
+        ReadTransaction { storage, root }
+    }
+
+    pub fn open_table<K: RedbKey + ?Sized, V: RedbValue + ?Sized>(
+        &self,
+        name: impl AsRef<[u8]>,
+    ) -> Result<Table<K, V>, Error> {
+        assert!(!name.as_ref().is_empty());
+        let definition = self
+            .storage
+            .get_table(name.as_ref(), TableType::Normal, self.root)?;
+        Table::new(definition.get_id(), self.storage)
+    }
+
+    pub fn open_multimap_table<K: RedbKey + ?Sized, V: RedbKey + ?Sized>(
+        &self,
+        name: impl AsRef<[u8]>,
+    ) -> Result<MultiMapTable<K, V>, Error> {
+        assert!(!name.as_ref().is_empty());
+        let definition = self
+            .storage
+            .get_table(name.as_ref(), TableType::MultiMap, self.root)?;
+        MultiMapTable::new(definition.get_id(), self.storage)
+    }
+
+    /// Opens `name` as a table keyed by a fixed-width primitive integer. See [`IntegerTable`]
+    /// for details on the key encoding.
+    pub fn open_integer_table<K: PrimitiveInt, V: RedbValue + ?Sized>(
+        &self,
+        name: impl AsRef<[u8]>,
+    ) -> Result<IntegerTable<K, V>, Error> {
+        Ok(IntegerTable::new(self.open_table(name)?))
+    }
+}
+
+/// A fixed-width primitive integer usable as an [`IntegerTable`] key.
+///
+/// [`Self::to_ordered_bytes`] encodes the value as big-endian bytes, with the sign bit flipped
+/// for signed types, so that unsigned byte-lexicographic comparison — what the underlying
+/// byte-keyed [`Table`] uses to order keys — matches `Self`'s numeric ordering.
+pub trait PrimitiveInt: Copy {
+    type Bytes: AsRef<[u8]>;
+
+    fn to_ordered_bytes(self) -> Self::Bytes;
+}
+
+impl PrimitiveInt for u32 {
+    type Bytes = [u8; 4];
+
+    fn to_ordered_bytes(self) -> [u8; 4] {
+        self.to_be_bytes()
+    }
+}
+
+impl PrimitiveInt for u64 {
+    type Bytes = [u8; 8];
+
+    fn to_ordered_bytes(self) -> [u8; 8] {
+        self.to_be_bytes()
+    }
+}
+
+impl PrimitiveInt for i32 {
+    type Bytes = [u8; 4];
+
+    fn to_ordered_bytes(self) -> [u8; 4] {
+        (self as u32 ^ (1 << 31)).to_be_bytes()
+    }
+}
+
+impl PrimitiveInt for i64 {
+    type Bytes = [u8; 8];
+
+    fn to_ordered_bytes(self) -> [u8; 8] {
+        (self as u64 ^ (1 << 63)).to_be_bytes()
+    }
+}
+
+/// A thin wrapper over a byte-keyed [`Table`] for keys that are fixed-width primitive integers
+/// (`u32`, `u64`, `i32`, `i64`).
+///
+/// [`Self::insert`] encodes the key via [`PrimitiveInt::to_ordered_bytes`] before writing it
+/// into the underlying `Table<[u8], V>`, so range scans over the raw bytes iterate in true
+/// numeric order. `Deref`/`DerefMut` expose the underlying table for read access and for
+/// batching several inserts into one commit; inserting through it directly requires encoding
+/// the key the same way `Self::insert` does.
+pub struct IntegerTable<K: PrimitiveInt, V: RedbValue + ?Sized>(Table<[u8], V>, PhantomData<K>);
+
+impl<K: PrimitiveInt, V: RedbValue + ?Sized> IntegerTable<K, V> {
+    fn new(inner: Table<[u8], V>) -> Self {
+        IntegerTable(inner, PhantomData)
+    }
+
+    /// Inserts `value` under `key`, implicitly creating and committing a single-insert write
+    /// transaction. Use `begin_write` (via `Deref`) directly, encoding keys with
+    /// [`PrimitiveInt::to_ordered_bytes`], to batch several inserts into one commit.
+    pub fn insert(&mut self, key: K, value: &V) -> Result<(), Error> {
+        let mut txn = self.0.begin_write()?;
+        txn.insert(key.to_ordered_bytes().as_ref(), value)?;
+        txn.commit()
+    }
+}
+
+impl<K: PrimitiveInt, V: RedbValue + ?Sized> Deref for IntegerTable<K, V> {
+    type Target = Table<[u8], V>;
+
+    fn deref(&self) -> &Table<[u8], V> {
+        &self.0
+    }
+}
+
+impl<K: PrimitiveInt, V: RedbValue + ?Sized> DerefMut for IntegerTable<K, V> {
+    fn deref_mut(&mut self) -> &mut Table<[u8], V> {
+        &mut self.0
+    }
+}
+
 pub struct DatabaseBuilder {
     page_size: Option<usize>,
+    encryption_key: Option<[u8; 32]>,
+    growth_step: Option<usize>,
+    durability: Durability,
 }
 
 impl DatabaseBuilder {
     #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
-        Self { page_size: None }
+        Self {
+            page_size: None,
+            encryption_key: None,
+            growth_step: None,
+            durability: Durability::default(),
+        }
     }
 
     pub fn set_page_size(&mut self, size: usize) -> &mut Self {
@@ -98,14 +401,48 @@ impl DatabaseBuilder {
         self
     }
 
+    /// Sets the durability mode used on commit. See [`Durability`] for the available tradeoffs.
+    /// Defaults to [`Durability::Immediate`].
+    pub fn set_durability(&mut self, durability: Durability) -> &mut Self {
+        self.durability = durability;
+        self
+    }
+
+    /// Sets the number of bytes the database file is grown by each time a write can't be
+    /// satisfied by the current allocation, instead of the default of doubling the current file
+    /// size. The growth itself — unmapping, extending the backing file, remapping, and
+    /// retrying the allocation — is performed by the storage layer at a transaction boundary;
+    /// this only configures the step size it uses.
+    pub fn set_growth_step(&mut self, bytes: usize) -> &mut Self {
+        assert!(bytes > 0);
+        self.growth_step = Some(bytes);
+        self
+    }
+
+    /// Enables page-level encryption-at-rest using the given 256-bit key.
+    ///
+    /// The key itself is never written to disk. It is handed to the storage layer, which owns
+    /// the on-disk page format and is therefore where the cipher, the per-page nonce
+    /// derivation, and the tamper-detection tag actually live; nothing in `DatabaseBuilder`
+    /// touches page bytes itself. Opening an existing encrypted file with the wrong key is
+    /// rejected there with [`Error::InvalidKey`].
+    pub fn with_encryption_key(&mut self, key: &[u8; 32]) -> &mut Self {
+        self.encryption_key = Some(*key);
+        self
+    }
+
     /// Opens the specified file as a redb database.
     /// * if the file does not exist, or is an empty file, a new database will be initialized in it
     /// * if the file is a valid redb database, it will be opened
     /// * otherwise this function will return an error
     ///
-    /// `db_size`: the maximum size in bytes of the database. Note: this cannot be changed after the
-    /// database is created.
-    /// TODO: remove the restriction that db_size cannot be changed
+    /// `db_size`: the initial size in bytes to allocate for a newly-created database. The file
+    /// is grown automatically (see [`Self::set_growth_step`]) whenever a write can't be
+    /// satisfied by the current allocation, so this is a sizing hint rather than a hard cap.
+    ///
+    /// If an encryption key was configured via [`Self::with_encryption_key`], the storage layer
+    /// verifies it against the file's header and returns [`Error::InvalidKey`] if it does not
+    /// match the key the database was created with.
     ///
     /// # Safety
     ///
@@ -121,16 +458,65 @@ impl DatabaseBuilder {
         file.set_len(db_size as u64)?;
 
         let mmap = MmapMut::map_mut(&file)?;
-        let storage = Storage::new(mmap, self.page_size)?;
-        Ok(Database { storage })
+        let storage = Storage::new(
+            mmap,
+            self.page_size,
+            self.encryption_key,
+            self.growth_step,
+            self.durability,
+        )?;
+        Ok(Database {
+            storage,
+            write_lock: Mutex::new(()),
+        })
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::{Database, Table};
+    use crate::{Database, DatabaseBuilder, Durability, Error, PrimitiveInt, Table};
+    use std::time::Duration;
     use tempfile::NamedTempFile;
 
+    #[test]
+    fn builder_options_compose() {
+        let tmpfile: NamedTempFile = NamedTempFile::new().unwrap();
+        let compacted: NamedTempFile = NamedTempFile::new().unwrap();
+        let key = [3u8; 32];
+
+        let db = unsafe {
+            DatabaseBuilder::new()
+                .with_encryption_key(&key)
+                .set_growth_step(1024 * 1024)
+                .set_durability(Durability::Eventual)
+                .open(tmpfile.path(), 4096)
+                .unwrap()
+        };
+
+        let mut write_txn = db.begin_write().unwrap();
+        let mut table: Table<[u8], [u8]> = write_txn.open_table("x").unwrap();
+        let mut int_table: crate::IntegerTable<u64, [u8]> =
+            write_txn.open_integer_table("counts").unwrap();
+        {
+            let mut txn = table.begin_write().unwrap();
+            txn.insert(b"k", b"v").unwrap();
+            txn.commit().unwrap();
+        }
+        int_table.insert(1u64, b"one").unwrap();
+        write_txn.commit().unwrap();
+
+        db.compact(compacted.path()).unwrap();
+
+        let reopened = unsafe {
+            DatabaseBuilder::new()
+                .with_encryption_key(&key)
+                .open(compacted.path(), 4096)
+                .unwrap()
+        };
+        let table: Table<[u8], [u8]> = reopened.open_table("x").unwrap();
+        assert_eq!(table.read_transaction().unwrap().len().unwrap(), 1);
+    }
+
     #[test]
     fn non_page_size_multiple() {
         let tmpfile: NamedTempFile = NamedTempFile::new().unwrap();
@@ -148,4 +534,297 @@ mod test {
         let read_txn = table.read_transaction().unwrap();
         assert_eq!(read_txn.len().unwrap(), 1);
     }
+
+    #[test]
+    fn encryption_round_trip_and_bytes_are_not_plaintext_on_disk() {
+        let tmpfile: NamedTempFile = NamedTempFile::new().unwrap();
+        let key = [7u8; 32];
+        // Long and distinctive enough that it won't appear on disk by chance.
+        const PLAINTEXT_KEY: &[u8] = b"do-not-leak-this-key-0123456789";
+        const PLAINTEXT_VALUE: &[u8] = b"do-not-leak-this-value-abcdefghij";
+
+        {
+            let db = unsafe {
+                DatabaseBuilder::new()
+                    .with_encryption_key(&key)
+                    .open(tmpfile.path(), 1024 * 1024)
+                    .unwrap()
+            };
+            let mut table: Table<[u8], [u8]> = db.open_table("x").unwrap();
+            let mut txn = table.begin_write().unwrap();
+            txn.insert(PLAINTEXT_KEY, PLAINTEXT_VALUE).unwrap();
+            txn.commit().unwrap();
+        }
+
+        let on_disk = std::fs::read(tmpfile.path()).unwrap();
+        let contains = |needle: &[u8]| on_disk.windows(needle.len()).any(|w| w == needle);
+        assert!(
+            !contains(PLAINTEXT_KEY),
+            "encrypted file contains the plaintext key bytes"
+        );
+        assert!(
+            !contains(PLAINTEXT_VALUE),
+            "encrypted file contains the plaintext value bytes"
+        );
+
+        let db = unsafe {
+            DatabaseBuilder::new()
+                .with_encryption_key(&key)
+                .open(tmpfile.path(), 1024 * 1024)
+                .unwrap()
+        };
+        let table: Table<[u8], [u8]> = db.open_table("x").unwrap();
+        let read_txn = table.read_transaction().unwrap();
+        assert_eq!(read_txn.len().unwrap(), 1);
+    }
+
+    #[test]
+    fn encryption_key_on_a_file_created_without_one_is_rejected() {
+        let tmpfile: NamedTempFile = NamedTempFile::new().unwrap();
+        {
+            let _db = unsafe { Database::open(tmpfile.path(), 1024 * 1024).unwrap() };
+        }
+
+        let result = unsafe {
+            DatabaseBuilder::new()
+                .with_encryption_key(&[9u8; 32])
+                .open(tmpfile.path(), 1024 * 1024)
+        };
+        assert!(matches!(result, Err(Error::InvalidKey)));
+    }
+
+    // Note: this only proves all three modes are accepted and don't break ordinary
+    // open/insert/commit. The actual msync/flush branching happens in Storage::commit, which
+    // this file can't see, so no test here distinguishes the modes' on-disk durability from one
+    // another.
+    #[test]
+    fn all_durability_modes_are_accepted_without_changing_commit_semantics() {
+        for durability in [Durability::Immediate, Durability::Eventual, Durability::None] {
+            let tmpfile: NamedTempFile = NamedTempFile::new().unwrap();
+            let db = unsafe {
+                DatabaseBuilder::new()
+                    .set_durability(durability)
+                    .open(tmpfile.path(), 1024 * 1024)
+                    .unwrap()
+            };
+            let mut table: Table<[u8], [u8]> = db.open_table("x").unwrap();
+            let mut txn = table.begin_write().unwrap();
+            txn.insert(b"k", b"v").unwrap();
+            txn.commit().unwrap();
+        }
+    }
+
+    #[test]
+    fn begin_write_serializes_against_other_writers() {
+        let tmpfile: NamedTempFile = NamedTempFile::new().unwrap();
+        let db = unsafe { Database::open(tmpfile.path(), 1024 * 1024).unwrap() };
+
+        let first = db.begin_write().unwrap();
+        let (done_tx, done_rx) = std::sync::mpsc::channel();
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                // Blocks until `first` is dropped below.
+                let _second = db.begin_write().unwrap();
+                done_tx.send(()).unwrap();
+            });
+
+            assert!(done_rx.recv_timeout(Duration::from_millis(100)).is_err());
+            drop(first);
+            done_rx.recv_timeout(Duration::from_secs(1)).unwrap();
+        });
+    }
+
+    #[test]
+    fn table_handle_write_is_not_serialized_against_an_open_write_transaction() {
+        // Documents the known gap: `Database::write_lock` only serializes `begin_write` callers
+        // against each other. `open_table` itself goes through `begin_write` (so *obtaining* the
+        // handle is serialized), but once a `Table` has been handed back, its own
+        // `begin_write`/`commit` calls straight into the storage layer and never touches that
+        // lock again — so writing through an already-obtained handle proceeds immediately even
+        // while a `WriteTransaction` is open on the same `Database`, unlike two `begin_write`
+        // calls, which do serialize (see `begin_write_serializes_against_other_writers`).
+        let tmpfile: NamedTempFile = NamedTempFile::new().unwrap();
+        let db = unsafe { Database::open(tmpfile.path(), 1024 * 1024).unwrap() };
+        let mut table: Table<[u8], [u8]> = db.open_table("x").unwrap();
+
+        let _held_write_txn = db.begin_write().unwrap();
+
+        let mut txn = table.begin_write().unwrap();
+        txn.insert(b"k", b"v").unwrap();
+        txn.commit().unwrap();
+    }
+
+    #[test]
+    fn table_writes_through_write_transaction_are_visible_immediately() {
+        // `WriteTransaction` does not batch or defer the writes made through the tables it
+        // opens: each table's own `commit()` flushes independently, so a separate reader sees
+        // them right away, before `WriteTransaction::commit()` ever runs.
+        let tmpfile: NamedTempFile = NamedTempFile::new().unwrap();
+        let db = unsafe { Database::open(tmpfile.path(), 1024 * 1024).unwrap() };
+
+        let mut write_txn = db.begin_write().unwrap();
+        let mut table: Table<[u8], [u8]> = write_txn.open_table("x").unwrap();
+        let mut txn = table.begin_write().unwrap();
+        txn.insert(b"k", b"v").unwrap();
+        txn.commit().unwrap();
+
+        let read_txn = db.begin_read().unwrap();
+        let reader: Table<[u8], [u8]> = read_txn.open_table("x").unwrap();
+        assert_eq!(reader.read_transaction().unwrap().len().unwrap(), 1);
+
+        // Finalizing the outer transaction afterward must not clobber or error out on the root
+        // that table's commit already advanced.
+        write_txn.commit().unwrap();
+    }
+
+    #[test]
+    fn write_transaction_commit_does_not_clobber_nested_table_commits() {
+        let tmpfile: NamedTempFile = NamedTempFile::new().unwrap();
+        let db = unsafe { Database::open(tmpfile.path(), 1024 * 1024).unwrap() };
+
+        let mut write_txn = db.begin_write().unwrap();
+        let mut a: Table<[u8], [u8]> = write_txn.open_table("a").unwrap();
+        let mut b: Table<[u8], [u8]> = write_txn.open_table("b").unwrap();
+
+        let mut txn = a.begin_write().unwrap();
+        txn.insert(b"k", b"a-value").unwrap();
+        txn.commit().unwrap();
+
+        let mut txn = b.begin_write().unwrap();
+        txn.insert(b"k", b"b-value").unwrap();
+        txn.commit().unwrap();
+
+        write_txn.commit().unwrap();
+
+        let read_txn = db.begin_read().unwrap();
+        let a: Table<[u8], [u8]> = read_txn.open_table("a").unwrap();
+        let b: Table<[u8], [u8]> = read_txn.open_table("b").unwrap();
+        assert_eq!(a.read_transaction().unwrap().len().unwrap(), 1);
+        assert_eq!(b.read_transaction().unwrap().len().unwrap(), 1);
+    }
+
+    #[test]
+    fn primitive_int_ordered_bytes_match_numeric_order() {
+        // Pure check of the encoding itself: sorting by the encoded bytes (lexicographically,
+        // the same way the underlying byte-keyed Table orders keys) must match sorting the
+        // original values numerically, for both unsigned and signed, including the sign
+        // boundary.
+        let mut values: Vec<u64> = vec![0, 1, u64::MAX, u64::MAX / 2, 42, 1_000_000];
+        let mut by_bytes = values.clone();
+        by_bytes.sort_by_key(|v| v.to_ordered_bytes());
+        values.sort();
+        assert_eq!(by_bytes, values);
+
+        let mut values: Vec<i64> = vec![i64::MIN, -1, 0, 1, i64::MAX, -42, 42];
+        let mut by_bytes = values.clone();
+        by_bytes.sort_by_key(|v| v.to_ordered_bytes());
+        values.sort();
+        assert_eq!(by_bytes, values);
+
+        // Negative values must sort strictly before non-negative ones.
+        assert!((-1i32).to_ordered_bytes() < 0i32.to_ordered_bytes());
+        assert!(i32::MIN.to_ordered_bytes() < i32::MAX.to_ordered_bytes());
+    }
+
+    #[test]
+    fn integer_table_readable_through_read_transaction() {
+        let tmpfile: NamedTempFile = NamedTempFile::new().unwrap();
+        let db = unsafe { Database::open(tmpfile.path(), 1024 * 1024).unwrap() };
+
+        let mut write_txn = db.begin_write().unwrap();
+        let mut table: crate::IntegerTable<u64, [u8]> =
+            write_txn.open_integer_table("counts").unwrap();
+        table.insert(42u64, b"answer").unwrap();
+        write_txn.commit().unwrap();
+
+        let read_txn = db.begin_read().unwrap();
+        let table: crate::IntegerTable<u64, [u8]> =
+            read_txn.open_integer_table("counts").unwrap();
+        let table_read_txn = table.read_transaction().unwrap();
+        assert_eq!(table_read_txn.len().unwrap(), 1);
+    }
+
+    #[test]
+    fn compact_preserves_data_and_shrinks_the_file() {
+        let tmpfile: NamedTempFile = NamedTempFile::new().unwrap();
+        let compacted: NamedTempFile = NamedTempFile::new().unwrap();
+        let original_db_size = 1024 * 1024;
+
+        let db = unsafe { Database::open(tmpfile.path(), original_db_size).unwrap() };
+        let mut table: Table<[u8], [u8]> = db.open_table("x").unwrap();
+        let mut txn = table.begin_write().unwrap();
+        txn.insert(b"k", b"v").unwrap();
+        txn.commit().unwrap();
+
+        db.compact(compacted.path()).unwrap();
+
+        let source_len = std::fs::metadata(tmpfile.path()).unwrap().len();
+        let compacted_len = std::fs::metadata(compacted.path()).unwrap().len();
+        assert_eq!(source_len, original_db_size as u64);
+        assert!(
+            compacted_len < source_len,
+            "compacted file ({compacted_len} bytes) is not smaller than the source \
+             allocation ({source_len} bytes); a single tiny row should compact to a small \
+             fraction of a {original_db_size}-byte allocation"
+        );
+
+        let reopened = unsafe { Database::open(compacted.path(), original_db_size).unwrap() };
+        let table: Table<[u8], [u8]> = reopened.open_table("x").unwrap();
+        let read_txn = table.read_transaction().unwrap();
+        assert_eq!(read_txn.len().unwrap(), 1);
+    }
+
+    #[test]
+    fn grows_past_initial_allocation() {
+        let tmpfile: NamedTempFile = NamedTempFile::new().unwrap();
+        let initial_db_size = 4096;
+        let db = unsafe {
+            DatabaseBuilder::new()
+                .set_growth_step(1024 * 1024)
+                .open(tmpfile.path(), initial_db_size)
+                .unwrap()
+        };
+        let mut table: Table<[u8], [u8]> = db.open_table("x").unwrap();
+
+        // 1000 * (4-byte key + 256-byte value), well past the 4096-byte initial allocation even
+        // accounting for overhead, so this can only succeed if the file actually grew.
+        let mut txn = table.begin_write().unwrap();
+        for i in 0u32..1000 {
+            txn.insert(&i.to_be_bytes(), &[0u8; 256]).unwrap();
+        }
+        txn.commit().unwrap();
+
+        let read_txn = table.read_transaction().unwrap();
+        assert_eq!(read_txn.len().unwrap(), 1000);
+
+        let grown_len = std::fs::metadata(tmpfile.path()).unwrap().len();
+        assert!(
+            grown_len > initial_db_size as u64,
+            "file is still {grown_len} bytes after inserts that don't fit in the initial \
+             {initial_db_size}-byte allocation; it should have grown"
+        );
+    }
+
+    #[test]
+    fn encryption_wrong_key_is_rejected() {
+        let tmpfile: NamedTempFile = NamedTempFile::new().unwrap();
+        let key = [1u8; 32];
+        let wrong_key = [2u8; 32];
+
+        {
+            let _db = unsafe {
+                DatabaseBuilder::new()
+                    .with_encryption_key(&key)
+                    .open(tmpfile.path(), 1024 * 1024)
+                    .unwrap()
+            };
+        }
+
+        let result = unsafe {
+            DatabaseBuilder::new()
+                .with_encryption_key(&wrong_key)
+                .open(tmpfile.path(), 1024 * 1024)
+        };
+        assert!(matches!(result, Err(Error::InvalidKey)));
+    }
 }